@@ -0,0 +1,206 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under the MIT license <LICENSE-MIT
+// http://opensource.org/licenses/MIT> or the Modified BSD license <LICENSE-BSD
+// https://opensource.org/licenses/BSD-3-Clause>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms. Please review the Licences for the
+// specific language governing permissions and limitations relating to use of the SAFE Network
+// Software.
+
+//! A generic BRBDataType wrapper for any rust-crdt CmRDT.
+//!
+//! This lets any CmRDT (`crdts::Map`, `crdts::LWWReg`, `crdts::GCounter`, ...) ride over
+//! Byzantine Reliable Broadcast without needing its own bespoke wrapper crate, as long as it
+//! implements `BrbValidatedOp` to supply the BFT checks that are specific to it.
+
+use std::collections::BTreeSet;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+use brb::BRBDataType;
+use crdts::{CmRDT, CvRDT, ResetRemove, VClock};
+use serde::Serialize;
+use thiserror::Error;
+
+/// The extra, BRB-specific checks a CmRDT must supply before its Op's are safe to broadcast.
+///
+/// `CmRDT::validate_op` only protects associativity/idempotency of the algorithm itself; it has
+/// no notion of *who* is allowed to have produced an Op, or of the network-relative timing BRB
+/// provides (per-source ordering, but not causal ordering across sources). Implement this trait
+/// to add those checks, e.g. "the source actor matches the dot attached to the operation".
+pub trait BrbValidatedOp<A>: CmRDT {
+    /// A validation error specific to this check.
+    type Error: std::error::Error + 'static;
+
+    /// Validates that `op`, received from `source`, is safe to apply.
+    fn validate_source(&self, source: &A, op: &Self::Op) -> Result<(), Self::Error>;
+}
+
+/// The extra, BRB-specific check a CvRDT must supply before a delta merged via `apply_delta` is
+/// safe to accept. Unlike a single Op, a delta has no one dot to authenticate against `source`,
+/// so implementors must instead reject any dot the delta introduces beyond what we already know
+/// that isn't attributable to `source`.
+pub trait BrbValidatedDelta<A>: CvRDT {
+    /// A validation error specific to this check.
+    type Error: std::error::Error + 'static;
+
+    /// Validates that `delta`, received from `source`, only introduces dots `source` is
+    /// authorized to have produced.
+    fn validate_delta_source(&self, source: &A, delta: &Self) -> Result<(), Self::Error>;
+}
+
+/// BRB wrapper for any CmRDT that supplies `BrbValidatedOp`.
+#[derive(Debug, Serialize, PartialEq, Eq, Clone)]
+pub struct BRBCmRdt<A: Hash + Ord + Clone, C> {
+    actor: A,
+    crdt: C,
+    /// Actors authorized to source operations, if membership is being enforced at this layer.
+    members: Option<BTreeSet<A>>,
+}
+
+impl<A: Hash + Ord + Clone, C> BRBCmRdt<A, C> {
+    /// Retrieves the BRB actor
+    pub fn actor(&self) -> &A {
+        &self.actor
+    }
+
+    /// Retrieves the underlying CRDT
+    pub fn crdt(&self) -> &C {
+        &self.crdt
+    }
+
+    /// Retrieves the underlying CRDT for in-crate local maintenance (e.g. `compact_stable`)
+    /// that bypasses the BRB-validated `BRBDataType::apply` path.
+    pub(crate) fn crdt_mut(&mut self) -> &mut C {
+        &mut self.crdt
+    }
+
+    /// Restricts `validate` to only accept ops sourced from one of `members`.
+    pub fn set_members(&mut self, members: impl IntoIterator<Item = A>) {
+        self.members = Some(members.into_iter().collect());
+    }
+
+    /// Returns the membership set currently being enforced, if `set_members` has been called.
+    pub fn members(&self) -> Option<&BTreeSet<A>> {
+        self.members.as_ref()
+    }
+
+    /// Produces just the state newer than `remote_clock`, for a peer to merge in via
+    /// `apply_delta` instead of transferring (or re-deriving from) the full CRDT state.
+    pub fn delta_since(&self, remote_clock: &VClock<A>) -> C
+    where
+        C: Clone + ResetRemove<A>,
+    {
+        let mut delta = self.crdt.clone();
+        delta.reset_remove(remote_clock);
+        delta
+    }
+
+    /// Validates and merges a delta produced by a peer's `delta_since`, enforcing the same
+    /// membership gate as `validate`, then `validate_delta_source` (the delta-shaped analogue of
+    /// `BrbValidatedOp::validate_source`) before running `validate_merge` the way `validate` runs
+    /// `validate_op`.
+    pub fn apply_delta(
+        &mut self,
+        source: &A,
+        delta: C,
+    ) -> Result<(), DeltaError<A, C::Validation, <C as BrbValidatedDelta<A>>::Error>>
+    where
+        C: CvRDT + BrbValidatedDelta<A>,
+        A: Debug,
+        C::Validation: Debug,
+    {
+        if let Some(members) = &self.members {
+            if !members.contains(source) {
+                return Err(DeltaError::SourceIsNotAMember(source.clone()));
+            }
+        }
+
+        self.crdt
+            .validate_delta_source(source, &delta)
+            .map_err(DeltaError::Source)?;
+        self.crdt.validate_merge(&delta).map_err(DeltaError::Merge)?;
+        self.crdt.merge(delta);
+        Ok(())
+    }
+}
+
+/// The variations that `apply_delta` may fail.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum DeltaError<A: Debug, MergeError: Debug + 'static, SourceError: std::error::Error + 'static>
+{
+    /// The source actor is not a member of the currently enforced membership set
+    #[error("{0:?} is not a member of the currently agreed group")]
+    SourceIsNotAMember(A),
+
+    /// The underlying CvRDT rejected the merge
+    ///
+    /// Like `ValidationError::Op`, this only requires `Debug`: `rust-crdt`'s `CvRDT::Validation`
+    /// types don't implement `std::error::Error`.
+    #[error("{0:?}")]
+    Merge(MergeError),
+
+    /// The delta introduced a dot not attributable to the claimed source
+    #[error(transparent)]
+    Source(SourceError),
+}
+
+/// The variations that a `BRBCmRdt` may fail validation.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ValidationError<A: Debug, OpError: Debug + 'static, SourceError: std::error::Error + 'static>
+{
+    /// The source actor is not a member of the currently enforced membership set
+    #[error("{0:?} is not a member of the currently agreed group")]
+    SourceIsNotAMember(A),
+
+    /// The underlying CmRDT rejected the op
+    ///
+    /// This only requires `Debug`, so a CmRDT whose `Validation` type does not implement
+    /// `std::error::Error` can still be wrapped; the error is shown with `{:?}` rather than
+    /// matching `Source`'s `#[error(transparent)]` treatment.
+    #[error("{0:?}")]
+    Op(OpError),
+
+    /// The op failed a BRB-specific source-authenticity or causal-readiness check
+    #[error(transparent)]
+    Source(SourceError),
+}
+
+impl<
+        A: Hash + Ord + Clone + Debug + Serialize + 'static,
+        C: CmRDT + BrbValidatedOp<A> + Default + Debug + Clone + PartialEq + Eq + 'static,
+    > BRBDataType<A> for BRBCmRdt<A, C>
+where
+    C::Op: Debug + Clone + Hash + Eq + Serialize,
+    C::Validation: Debug,
+    <C as BrbValidatedOp<A>>::Error: std::error::Error,
+{
+    type Op = C::Op;
+    type ValidationError =
+        ValidationError<A, <C as CmRDT>::Validation, <C as BrbValidatedOp<A>>::Error>;
+
+    fn new(actor: A) -> Self {
+        BRBCmRdt {
+            actor,
+            crdt: Default::default(),
+            members: None,
+        }
+    }
+
+    fn validate(&self, source: &A, op: &Self::Op) -> Result<(), Self::ValidationError> {
+        if let Some(members) = &self.members {
+            if !members.contains(source) {
+                return Err(ValidationError::SourceIsNotAMember(source.clone()));
+            }
+        }
+
+        self.crdt.validate_op(op).map_err(ValidationError::Op)?;
+        self.crdt
+            .validate_source(source, op)
+            .map_err(ValidationError::Source)
+    }
+
+    fn apply(&mut self, op: Self::Op) {
+        self.crdt.apply(op);
+    }
+}
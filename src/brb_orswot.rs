@@ -10,123 +10,148 @@
 //! A BRBDataType wrapper for an ORSWOT from rust-crdt.
 //!
 //! This enables ORSWOT CRDT operations to be transmitted in a BFT manner using
-//! Byzantine Reliable Broadcast.
+//! Byzantine Reliable Broadcast. `BRBOrswot` is an instance of the generic `BRBCmRdt` wrapper;
+//! this module only needs to supply the ORSWOT-specific convenience methods and the
+//! `BrbValidatedOp` and `BrbValidatedDelta` impls that tell `BRBCmRdt` how to authenticate the
+//! source of an ORSWOT op or delta.
 
-use std::cmp::Ordering;
 use std::collections::HashSet;
 use std::{fmt::Debug, hash::Hash};
 
-use brb::BRBDataType;
-use crdts::{orswot, CmRDT};
-use serde::Serialize;
+use crdts::{orswot, CmRDT, Dot, VClock};
 use thiserror::Error;
 
+use crate::brb_cm_rdt::{BrbValidatedDelta, BrbValidatedOp, BRBCmRdt};
+
 /// BRB wrapper for an Orswot CRDT
-#[derive(Debug, Serialize, PartialEq, Eq, Clone)]
-pub struct BRBOrswot<A: Hash + Ord + Clone, M: Clone + Eq + Hash> {
-    actor: A,
-    orswot: orswot::Orswot<M, A>,
-}
+pub type BRBOrswot<A, M> = BRBCmRdt<A, orswot::Orswot<M, A>>;
 
 impl<A: Hash + Ord + Clone + Debug, M: Clone + Eq + Hash> BRBOrswot<A, M> {
     /// Generates an Orswot Add operation. (but does not apply it)
     pub fn add(&self, member: M) -> orswot::Op<M, A> {
-        let add_ctx = self.orswot.read_ctx().derive_add_ctx(self.actor.clone());
-        self.orswot.add(member, add_ctx)
+        let add_ctx = self.crdt().read_ctx().derive_add_ctx(self.actor().clone());
+        self.crdt().add(member, add_ctx)
+    }
+
+    /// Generates an Orswot Add operation covering several members at once. (but does not apply it)
+    pub fn add_all(&self, members: impl IntoIterator<Item = M>) -> orswot::Op<M, A> {
+        let add_ctx = self.crdt().read_ctx().derive_add_ctx(self.actor().clone());
+        self.crdt().add_all(members, add_ctx)
     }
 
     /// Generates an Orswot Rm operation. (but does not apply it)
     pub fn rm(&self, member: M) -> orswot::Op<M, A> {
-        let rm_ctx = self.orswot.read_ctx().derive_rm_ctx();
-        self.orswot.rm(member, rm_ctx)
+        let rm_ctx = self.crdt().read_ctx().derive_rm_ctx();
+        self.crdt().rm(member, rm_ctx)
     }
 
-    /// Check if the set contains a member
-    pub fn contains(&self, member: &M) -> bool {
-        self.orswot.contains(member).val
+    /// Generates an Orswot Rm operation covering several members at once. (but does not apply it)
+    pub fn rm_all(&self, members: impl IntoIterator<Item = M>) -> orswot::Op<M, A> {
+        let rm_ctx = self.crdt().read_ctx().derive_rm_ctx();
+        self.crdt().rm_all(members, rm_ctx)
     }
 
-    /// Retrieves the BRB actor
-    pub fn actor(&self) -> &A {
-        &self.actor
+    /// Check if the set contains a member
+    pub fn contains(&self, member: &M) -> bool {
+        self.crdt().contains(member).val
     }
 
     /// Retrieves the underlying orswot
     pub fn orswot(&self) -> &orswot::Orswot<M, A> {
-        &self.orswot
+        self.crdt()
     }
 
     /// Read from the underlying orswot
     pub fn read(&self) -> HashSet<M> {
-        self.orswot.read().val
+        self.crdt().read().val
+    }
+
+    /// Collapses any member's witness dots that are all `<= stable_clock` down to one, since a
+    /// dot every current member has already observed no longer needs more than one survivor to
+    /// remain a sufficient witness for `read()` and for a future remove's causal check.
+    pub fn compact_stable(&mut self, stable_clock: &VClock<A>) {
+        let compactable: Vec<(M, VClock<A>)> = self
+            .orswot()
+            .iter()
+            .filter_map(|ctx| {
+                let witness = ctx.rm_clock;
+                if witness.dots.len() > 1 && witness <= *stable_clock {
+                    Some((ctx.val.clone(), witness))
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        for (member, witness) in compactable {
+            let keep = witness
+                .dots
+                .keys()
+                .next()
+                .expect("witness.dots.len() > 1 was just checked")
+                .clone();
+
+            let dots_to_drop: VClock<A> = witness
+                .dots
+                .into_iter()
+                .filter(|(actor, _)| *actor != keep)
+                .map(|(actor, counter)| Dot::new(actor, counter))
+                .collect();
+
+            self.crdt_mut().apply(orswot::Op::Rm {
+                clock: dots_to_drop,
+                members: vec![member],
+            });
+        }
     }
 }
 
+/// The variations that an ORSWOT op may fail BRB's source-authenticity check.
 #[derive(Error, Debug, PartialEq, Eq)]
-pub enum ValidationError<E: std::error::Error + 'static> {
+pub enum OrswotValidationError {
     /// The source actor is not the same as the dot attached to the operation
     #[error("The source actor is not the same as the dot attached to the operation")]
     SourceDoesNotMatchOp,
-
-    /// Attempted to remove more than one member, this is not currently supported
-    #[error("Attempted to remove more than one member, this is not currently supported")]
-    RemoveOnlySupportedForOneMember,
-
-    /// Attempt to remove a member that we have not seen yet
-    #[error("Attempt to remove a member that we have not seen yet")]
-    RemovingDataWeHaventSeenYet,
-
-    /// Orswot validation error
-    #[error(transparent)]
-    Orswot(#[from] E),
 }
 
-impl<
-        A: Hash + Ord + Clone + Debug + Serialize + 'static,
-        M: Clone + Eq + Hash + Debug + Serialize,
-    > BRBDataType<A> for BRBOrswot<A, M>
-{
-    type Op = orswot::Op<M, A>;
-    type ValidationError = ValidationError<<orswot::Orswot<M, A> as CmRDT>::Validation>;
-
-    fn new(actor: A) -> Self {
-        BRBOrswot {
-            actor,
-            orswot: Default::default(),
+impl<A: Hash + Ord + Clone + Debug, M: Clone + Eq + Hash> BrbValidatedDelta<A> for orswot::Orswot<M, A> {
+    type Error = OrswotValidationError;
+
+    fn validate_delta_source(&self, source: &A, delta: &Self) -> Result<(), Self::Error> {
+        let known = self.clock();
+        let incoming = delta.clock();
+        for (actor, counter) in incoming.dots.iter() {
+            let already_known = known.dots.get(actor).copied().unwrap_or(0) >= *counter;
+            if actor != source && !already_known {
+                return Err(OrswotValidationError::SourceDoesNotMatchOp);
+            }
         }
+        Ok(())
     }
+}
 
-    fn validate(&self, source: &A, op: &Self::Op) -> Result<(), Self::ValidationError> {
-        self.orswot
-            .validate_op(&op)
-            .map_err(ValidationError::Orswot)?;
+impl<A: Hash + Ord + Clone + Debug, M: Clone + Eq + Hash> BrbValidatedOp<A> for orswot::Orswot<M, A> {
+    type Error = OrswotValidationError;
 
+    fn validate_source(&self, source: &A, op: &Self::Op) -> Result<(), Self::Error> {
         match op {
             orswot::Op::Add { dot, members: _ } => {
                 if &dot.actor != source {
-                    Err(ValidationError::SourceDoesNotMatchOp)
+                    Err(OrswotValidationError::SourceDoesNotMatchOp)
                 } else {
                     Ok(())
                 }
             }
-            orswot::Op::Rm { clock, members } => {
-                if members.len() != 1 {
-                    Err(ValidationError::RemoveOnlySupportedForOneMember)
-                } else if matches!(
-                    clock.partial_cmp(&self.orswot.clock()),
-                    None | Some(Ordering::Greater)
-                ) {
-                    // NOTE: this check renders all the "deferred_remove" logic in the ORSWOT obsolete.
-                    //       The deferred removes would buffer these out-of-order removes.
-                    Err(ValidationError::RemovingDataWeHaventSeenYet)
-                } else {
-                    Ok(())
-                }
+            orswot::Op::Rm { .. } => {
+                // NOTE: a remove's witnessing clock may legitimately be `Greater` than or
+                // incomparable to our own, since BRB only guarantees per-source ordering and
+                // a causally-valid remove can reference dots from several actors. We accept
+                // it here and let the ORSWOT's own `deferred` buffer hold it until the adds
+                // it depends on have been applied (see `Orswot::apply_rm`). This applies
+                // equally whether the op removes one member or a whole batch at once, since
+                // every removed member shares the same witnessing clock.
+                Ok(())
             }
         }
     }
-
-    fn apply(&mut self, op: Self::Op) {
-        self.orswot.apply(op);
-    }
 }
@@ -0,0 +1,18 @@
+// Copyright 2021 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under the MIT license <LICENSE-MIT
+// http://opensource.org/licenses/MIT> or the Modified BSD license <LICENSE-BSD
+// https://opensource.org/licenses/BSD-3-Clause>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms. Please review the Licences for the
+// specific language governing permissions and limitations relating to use of the SAFE Network
+// Software.
+
+//! A BRB Wrapper for the Orswot CRDT
+
+#![deny(missing_docs)]
+
+mod brb_cm_rdt;
+mod brb_orswot;
+
+pub use brb_cm_rdt::{BRBCmRdt, BrbValidatedDelta, BrbValidatedOp, DeltaError, ValidationError};
+pub use brb_orswot::{BRBOrswot, OrswotValidationError};
@@ -0,0 +1,98 @@
+//! Exercises `BRBCmRdt<A, C>` directly with a CmRDT other than `orswot::Orswot`, to prove the
+//! wrapper is actually generic rather than only ever instantiated as `BRBOrswot`.
+
+use std::collections::BTreeMap;
+
+use brb::BRBDataType;
+use brb_dt_orswot::{BRBCmRdt, BrbValidatedOp};
+use crdts::CmRDT;
+use serde::Serialize;
+use thiserror::Error;
+
+/// A minimal grow-only counter CmRDT: each actor may only increment its own entry.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+struct GCounter<A: Ord> {
+    counts: BTreeMap<A, u64>,
+}
+
+impl<A: Ord + Clone> GCounter<A> {
+    fn read(&self) -> u64 {
+        self.counts.values().sum()
+    }
+}
+
+/// An increment of `amount` attributed to `actor`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+struct Incr<A> {
+    actor: A,
+    amount: u64,
+}
+
+/// The ways a `GCounter` op may fail `CmRDT::validate_op`.
+#[derive(Error, Debug, PartialEq, Eq)]
+enum GCounterValidation {
+    /// An increment of zero is never useful and is rejected as malformed.
+    #[error("increment amount must be non-zero")]
+    ZeroIncrement,
+}
+
+impl<A: Ord + Clone> CmRDT for GCounter<A> {
+    type Op = Incr<A>;
+    type Validation = GCounterValidation;
+
+    fn validate_op(&self, op: &Self::Op) -> Result<(), Self::Validation> {
+        if op.amount == 0 {
+            Err(GCounterValidation::ZeroIncrement)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn apply(&mut self, op: Self::Op) {
+        *self.counts.entry(op.actor).or_insert(0) += op.amount;
+    }
+}
+
+/// The ways a `GCounter` op may fail BRB's source-authenticity check.
+#[derive(Error, Debug, PartialEq, Eq)]
+enum GCounterValidationError {
+    /// The source actor is not the same as the actor attached to the increment
+    #[error("The source actor is not the same as the actor attached to the increment")]
+    SourceDoesNotMatchOp,
+}
+
+impl<A: Ord + Clone + PartialEq> BrbValidatedOp<A> for GCounter<A> {
+    type Error = GCounterValidationError;
+
+    fn validate_source(&self, source: &A, op: &Self::Op) -> Result<(), Self::Error> {
+        if &op.actor != source {
+            Err(GCounterValidationError::SourceDoesNotMatchOp)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+type BRBGCounter<A> = BRBCmRdt<A, GCounter<A>>;
+
+#[test]
+fn a_gcounter_rides_over_brb_cm_rdt_like_an_orswot_does() {
+    let mut counter: BRBGCounter<&'static str> = BRBDataType::new("a");
+
+    let incr_op = Incr { actor: "a", amount: 3 };
+    counter.validate(&"a", &incr_op).expect("a self-sourced increment should validate");
+    counter.apply(incr_op);
+
+    assert_eq!(counter.crdt().read(), 3);
+}
+
+#[test]
+fn validate_rejects_an_increment_attributed_to_another_actor() {
+    let counter: BRBGCounter<&'static str> = BRBDataType::new("a");
+
+    let incr_op = Incr { actor: "a", amount: 1 };
+    match counter.validate(&"mallory", &incr_op) {
+        Err(_) => {}
+        Ok(()) => panic!("expected validate to reject an op sourced from a different actor"),
+    }
+}
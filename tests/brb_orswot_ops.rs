@@ -0,0 +1,162 @@
+//! Exercises `BRBOrswot`/`BRBCmRdt` directly, without the secure-broadcast network harness
+//! `tests/dsb_orswot_net.rs` is wired up for (that file drives a different, sibling `SBOrswot`
+//! type and isn't part of this crate's own surface).
+
+use std::collections::HashSet;
+
+use brb::BRBDataType;
+use brb_dt_orswot::{BRBOrswot, DeltaError, ValidationError};
+use crdts::{orswot, CmRDT, Dot, VClock};
+
+#[test]
+fn add_all_and_rm_all_apply_as_a_single_batch_op() {
+    let mut orswot: BRBOrswot<&'static str, u8> = BRBDataType::new("a");
+
+    let add_op = orswot.add_all(vec![1, 2, 3]);
+    orswot.validate(&"a", &add_op).expect("batch add should validate");
+    orswot.apply(add_op);
+    assert_eq!(orswot.read(), [1, 2, 3].iter().cloned().collect::<HashSet<_>>());
+
+    let rm_op = orswot.rm_all(vec![1, 2]);
+    orswot.validate(&"a", &rm_op).expect("batch rm should validate");
+    orswot.apply(rm_op);
+    assert_eq!(orswot.read(), [3].iter().cloned().collect::<HashSet<_>>());
+}
+
+#[test]
+fn a_remove_received_before_its_add_is_buffered_until_the_add_lands() {
+    let mut orswot: BRBOrswot<&'static str, &'static str> = BRBDataType::new("b");
+
+    // "a" is about to add "x" at dot (a, 1), but we receive the remove that subsumes it first.
+    let mut premature_clock = VClock::new();
+    premature_clock.apply(Dot::new("a", 1));
+    let rm_op = orswot::Op::Rm {
+        clock: premature_clock,
+        members: vec!["x"],
+    };
+
+    orswot.validate(&"a", &rm_op).expect("an early rm is still causally well-formed");
+    orswot.apply(rm_op);
+
+    // "x" was never added, so there's nothing to remove yet.
+    assert!(!orswot.contains(&"x"));
+
+    let add_op = orswot::Op::Add {
+        dot: Dot::new("a", 1),
+        members: vec!["x"],
+    };
+    orswot.validate(&"a", &add_op).expect("the add should validate");
+    orswot.apply(add_op);
+
+    // the buffered remove replays as soon as the add it was waiting on is applied.
+    assert!(!orswot.contains(&"x"));
+}
+
+#[test]
+fn validate_rejects_ops_from_actors_outside_the_enforced_membership() {
+    let mut orswot: BRBOrswot<&'static str, u8> = BRBDataType::new("a");
+    orswot.set_members(vec!["a", "b"]);
+
+    let add_op = orswot.add(1);
+    orswot.validate(&"a", &add_op).expect("a member's op should validate");
+
+    match orswot.validate(&"mallory", &add_op) {
+        Err(ValidationError::SourceIsNotAMember(actor)) => assert_eq!(actor, "mallory"),
+        other => panic!("expected SourceIsNotAMember, got {:?}", other),
+    }
+}
+
+#[test]
+fn apply_delta_transfers_only_the_difference_since_a_remote_clock() {
+    let mut origin: BRBOrswot<&'static str, u8> = BRBDataType::new("origin");
+    let add_op = origin.add_all(vec![1, 2, 3]);
+    origin.validate(&"origin", &add_op).unwrap();
+    origin.apply(add_op);
+
+    let mut replica: BRBOrswot<&'static str, u8> = BRBDataType::new("replica");
+    let delta = origin.delta_since(&replica.orswot().clock());
+    replica
+        .apply_delta(&"origin", delta)
+        .expect("a delta from a known source should merge");
+
+    assert_eq!(replica.read(), origin.read());
+}
+
+#[test]
+fn apply_delta_rejects_a_source_outside_the_enforced_membership() {
+    let mut origin: BRBOrswot<&'static str, u8> = BRBDataType::new("origin");
+    let add_op = origin.add(1);
+    origin.validate(&"origin", &add_op).unwrap();
+    origin.apply(add_op);
+
+    let mut replica: BRBOrswot<&'static str, u8> = BRBDataType::new("replica");
+    replica.set_members(vec!["origin"]);
+
+    let delta = origin.delta_since(&replica.orswot().clock());
+    match replica.apply_delta(&"mallory", delta) {
+        Err(DeltaError::SourceIsNotAMember(actor)) => assert_eq!(actor, "mallory"),
+        other => panic!("expected SourceIsNotAMember, got {:?}", other),
+    }
+}
+
+#[test]
+fn apply_delta_rejects_a_dot_forged_under_another_actors_name() {
+    // "mallory" is a legitimate member, but crafts a delta claiming an add from "victim" that
+    // "victim" never made.
+    let mut forged: orswot::Orswot<u8, &'static str> = Default::default();
+    forged.apply(orswot::Op::Add {
+        dot: Dot::new("victim", 1),
+        members: vec![42],
+    });
+
+    let mut replica: BRBOrswot<&'static str, u8> = BRBDataType::new("replica");
+    replica.set_members(vec!["mallory", "victim"]);
+
+    match replica.apply_delta(&"mallory", forged) {
+        Err(DeltaError::Source(_)) => {}
+        other => panic!("expected the forged dot to be rejected, got {:?}", other),
+    }
+    assert!(!replica.contains(&42));
+}
+
+#[test]
+fn compact_stable_collapses_dominated_witness_dots_without_changing_read() {
+    let mut a: BRBOrswot<&'static str, u8> = BRBDataType::new("a");
+    let add_op = a.add(1);
+    a.validate(&"a", &add_op).unwrap();
+    a.apply(add_op);
+
+    let mut b: BRBOrswot<&'static str, u8> = BRBDataType::new("b");
+    let delta = a.delta_since(&b.orswot().clock());
+    b.apply_delta(&"a", delta).unwrap();
+
+    // re-add the same member from "b" after merging "a"'s add, so its witness clock now
+    // carries dots from both actors.
+    let add_op_b = b.add(1);
+    b.validate(&"b", &add_op_b).unwrap();
+    b.apply(add_op_b);
+
+    let witness_before = b
+        .orswot()
+        .iter()
+        .find(|ctx| *ctx.val == 1)
+        .map(|ctx| ctx.rm_clock)
+        .expect("member 1 should be present");
+    assert!(witness_before.dots.len() > 1);
+
+    // once the stability frontier dominates every dot witnessing the member, compaction
+    // should collapse it down to a single dot while leaving `read()` untouched.
+    let stable_clock = b.orswot().clock();
+    b.compact_stable(&stable_clock);
+
+    assert!(b.contains(&1));
+    assert_eq!(b.read(), [1].iter().cloned().collect::<HashSet<_>>());
+
+    let witness_after = b
+        .orswot()
+        .iter()
+        .find(|ctx| *ctx.val == 1)
+        .map(|ctx| ctx.rm_clock)
+        .expect("member 1 should still be present after compaction");
+    assert_eq!(witness_after.dots.len(), 1);
+}